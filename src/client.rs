@@ -0,0 +1,70 @@
+use crate::metrics::Metrics;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A handle to a single worker's socket, as handed out by the load balancer.
+///
+/// A `Client` can carry any number of in-flight counters (e.g. the one a strategy like
+/// [`LeastConnections`](crate::load_balancing::least_connections::LeastConnections) uses to pick
+/// the next worker, and the one [`Metrics`](crate::metrics::Metrics) uses for the `/metrics`
+/// gauge) — each is incremented when tracked and decremented when this `Client` is dropped. It
+/// can also carry a latency tracker, set by [`LoadBalancer::get_client`](crate::load_balancing::LoadBalancer::get_client),
+/// so `faucet_worker_request_duration_seconds` measures the full time the caller holds this
+/// `Client` (i.e. the actual request against the worker) rather than just the time spent picking
+/// which worker to use.
+pub(crate) struct Client {
+    pub(crate) socket_addr: SocketAddr,
+    in_flight_guards: Vec<Arc<AtomicUsize>>,
+    latency: Option<(Arc<Metrics>, Instant)>,
+}
+
+impl Client {
+    pub(crate) fn new(socket_addr: SocketAddr) -> Self {
+        Self {
+            socket_addr,
+            in_flight_guards: Vec::new(),
+            latency: None,
+        }
+    }
+
+    /// A `Client` that increments `in_flight` now and decrements it once dropped.
+    pub(crate) fn with_in_flight_guard(socket_addr: SocketAddr, in_flight: Arc<AtomicUsize>) -> Self {
+        let mut client = Self::new(socket_addr);
+        client.track_in_flight(in_flight);
+        client
+    }
+
+    /// Increments `in_flight` now; it is decremented when this `Client` is dropped.
+    pub(crate) fn track_in_flight(&mut self, in_flight: Arc<AtomicUsize>) {
+        in_flight.fetch_add(1, Ordering::Relaxed);
+        self.in_flight_guards.push(in_flight);
+    }
+
+    /// Starts timing this `Client`'s lifetime; the elapsed duration is recorded to `metrics` as
+    /// this worker's request latency once the `Client` is dropped.
+    pub(crate) fn track_latency(&mut self, metrics: Arc<Metrics>) {
+        self.latency = Some((metrics, Instant::now()));
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        for in_flight in &self.in_flight_guards {
+            in_flight.fetch_sub(1, Ordering::Relaxed);
+        }
+        if let Some((metrics, started_at)) = &self.latency {
+            metrics.record_routed(self.socket_addr, started_at.elapsed());
+        }
+    }
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("socket_addr", &self.socket_addr)
+            .field("in_flight_guards", &self.in_flight_guards.len())
+            .finish()
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt;
+
+pub type FaucetResult<T> = Result<T, FaucetError>;
+
+#[derive(Debug)]
+pub enum FaucetError {
+    Io(std::io::Error),
+    Hyper(hyper::Error),
+    Unknown(String),
+}
+
+impl fmt::Display for FaucetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaucetError::Io(e) => write!(f, "IO error: {e}"),
+            FaucetError::Hyper(e) => write!(f, "Hyper error: {e}"),
+            FaucetError::Unknown(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for FaucetError {}
+
+impl From<std::io::Error> for FaucetError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<hyper::Error> for FaucetError {
+    fn from(e: hyper::Error) -> Self {
+        Self::Hyper(e)
+    }
+}
@@ -0,0 +1,6 @@
+mod client;
+mod error;
+mod load_balancing;
+mod metrics;
+mod rate_limit;
+mod worker;
@@ -0,0 +1,28 @@
+use crate::error::{FaucetError, FaucetResult};
+use hyper::body::Incoming;
+use hyper::Request;
+use std::net::{IpAddr, SocketAddr};
+
+/// Determines how the client IP used for load-balancing decisions is obtained.
+#[derive(Clone, Copy, Debug)]
+pub enum IpExtractor {
+    /// Use the peer address of the accepted connection.
+    Direct,
+    /// Trust a header set by an upstream proxy (e.g. `X-Forwarded-For`).
+    Header(&'static str),
+}
+
+impl IpExtractor {
+    pub fn extract(&self, request: &Request<Incoming>, socket: SocketAddr) -> FaucetResult<IpAddr> {
+        match self {
+            IpExtractor::Direct => Ok(socket.ip()),
+            IpExtractor::Header(name) => request
+                .headers()
+                .get(*name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split(',').next())
+                .and_then(|value| value.trim().parse().ok())
+                .ok_or_else(|| FaucetError::Unknown(format!("Missing or invalid {name} header"))),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use super::{least_recently_failed, no_workers_error, LoadBalancingStrategy};
+use crate::client::Client;
+use crate::error::{FaucetError, FaucetResult};
+use crate::worker::SharedWorkerStates;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
+
+pub(crate) struct IpHash {
+    states: SharedWorkerStates,
+}
+
+impl IpHash {
+    pub(crate) fn new(states: SharedWorkerStates) -> FaucetResult<Self> {
+        if states.read().expect("lock poisoned").is_empty() {
+            return Err(FaucetError::Unknown(
+                "Cannot balance between zero workers".into(),
+            ));
+        }
+        Ok(Self { states })
+    }
+}
+
+fn hash_ip(ip: IpAddr) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    ip.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[async_trait::async_trait]
+impl LoadBalancingStrategy for IpHash {
+    async fn entry(&self, ip: IpAddr) -> FaucetResult<Client> {
+        let workers = self.states.read().expect("lock poisoned").clone();
+        if workers.is_empty() {
+            return Err(no_workers_error());
+        }
+        let start = hash_ip(ip) as usize % workers.len();
+        for offset in 0..workers.len() {
+            let candidate = &workers[(start + offset) % workers.len()];
+            if candidate.is_healthy() {
+                return Ok(candidate.client());
+            }
+        }
+        Ok(least_recently_failed(&workers, start)
+            .expect("workers is non-empty, checked above")
+            .client())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::{WorkerState, WorkerStatus};
+    use std::sync::{Arc, RwLock};
+
+    fn addr(port: u16) -> std::net::SocketAddr {
+        format!("127.0.0.1:{port}").parse().unwrap()
+    }
+
+    fn states(workers: Vec<WorkerState>) -> SharedWorkerStates {
+        Arc::new(RwLock::new(workers))
+    }
+
+    #[tokio::test]
+    async fn skips_unhealthy_workers() {
+        let states = states(vec![
+            WorkerState::new_for_test(addr(9001), WorkerStatus::Unhealthy, None),
+            WorkerState::new_for_test(addr(9002), WorkerStatus::Healthy, None),
+            WorkerState::new_for_test(addr(9003), WorkerStatus::Starting, None),
+        ]);
+        let strategy = IpHash::new(states).unwrap();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        for _ in 0..5 {
+            let client = strategy.entry(ip).await.unwrap();
+            assert_eq!(client.socket_addr, addr(9002));
+        }
+    }
+
+    #[tokio::test]
+    async fn falls_back_and_spreads_across_ties_when_no_worker_is_healthy() {
+        let states = states(vec![
+            WorkerState::new_for_test(addr(9001), WorkerStatus::Starting, None),
+            WorkerState::new_for_test(addr(9002), WorkerStatus::Starting, None),
+            WorkerState::new_for_test(addr(9003), WorkerStatus::Starting, None),
+        ]);
+        let strategy = IpHash::new(states).unwrap();
+        // Every worker tied at "never failed", so different clients (who hash to different
+        // starting offsets) must land on different fallback workers rather than all piling
+        // onto one fixed worker.
+        let mut seen = std::collections::HashSet::new();
+        for last_octet in 1..=20u8 {
+            let ip: IpAddr = std::net::Ipv4Addr::new(10, 0, 0, last_octet).into();
+            let client = strategy.entry(ip).await.unwrap();
+            seen.insert(client.socket_addr);
+        }
+        assert_eq!(seen.len(), 3);
+    }
+}
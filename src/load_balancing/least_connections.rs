@@ -0,0 +1,152 @@
+use super::{least_recently_failed, no_workers_error, LoadBalancingStrategy};
+use crate::client::Client;
+use crate::error::{FaucetError, FaucetResult};
+use crate::worker::{SharedWorkerStates, WorkerState};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+pub(crate) struct LeastConnections {
+    states: SharedWorkerStates,
+    /// In-flight counters, keyed by socket address so they survive a read of a new generation
+    /// of `states` (e.g. after `Workers::scale_to` or `Workers::restart`). Pruned on every
+    /// `entry` call so addresses that have left the live set don't linger forever.
+    in_flight: Mutex<HashMap<SocketAddr, Arc<AtomicUsize>>>,
+    index: AtomicUsize,
+}
+
+impl LeastConnections {
+    pub(crate) fn new(states: SharedWorkerStates) -> FaucetResult<Self> {
+        if states.read().expect("lock poisoned").is_empty() {
+            return Err(FaucetError::Unknown(
+                "Cannot balance between zero workers".into(),
+            ));
+        }
+        Ok(Self {
+            states,
+            in_flight: Mutex::new(HashMap::new()),
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    fn counter_for(&self, addr: SocketAddr) -> Arc<AtomicUsize> {
+        Arc::clone(
+            self.in_flight
+                .lock()
+                .expect("lock poisoned")
+                .entry(addr)
+                .or_insert_with(|| Arc::new(AtomicUsize::new(0))),
+        )
+    }
+
+    /// Drops counters for addresses no longer present in `live`, so a restarted or scaled-down
+    /// worker's entry doesn't stay in the map forever.
+    fn prune_in_flight(&self, live: &[WorkerState]) {
+        self.in_flight
+            .lock()
+            .expect("lock poisoned")
+            .retain(|addr, _| live.iter().any(|w| &w.socket_addr == addr));
+    }
+}
+
+#[async_trait::async_trait]
+impl LoadBalancingStrategy for LeastConnections {
+    async fn entry(&self, _ip: IpAddr) -> FaucetResult<Client> {
+        let workers = self.states.read().expect("lock poisoned").clone();
+        if workers.is_empty() {
+            return Err(no_workers_error());
+        }
+        self.prune_in_flight(&workers);
+
+        let index = self.index.fetch_add(1, Ordering::Relaxed);
+
+        let healthy: Vec<_> = workers.iter().filter(|w| w.is_healthy()).collect();
+
+        if healthy.is_empty() {
+            let chosen =
+                least_recently_failed(&workers, index).expect("workers is non-empty, checked above");
+            return Ok(Client::with_in_flight_guard(
+                chosen.socket_addr,
+                self.counter_for(chosen.socket_addr),
+            ));
+        }
+
+        // Break ties between equally-loaded workers by rotating the scan start, so repeated
+        // calls spread evenly rather than always favoring the first candidate in the list.
+        let start = index % healthy.len();
+        let chosen = (0..healthy.len())
+            .map(|offset| healthy[(start + offset) % healthy.len()])
+            .min_by_key(|w| self.counter_for(w.socket_addr).load(Ordering::Relaxed))
+            .expect("healthy should not be empty");
+
+        Ok(Client::with_in_flight_guard(
+            chosen.socket_addr,
+            self.counter_for(chosen.socket_addr),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::WorkerStatus;
+    use std::sync::RwLock;
+
+    fn addr(port: u16) -> SocketAddr {
+        format!("127.0.0.1:{port}").parse().unwrap()
+    }
+
+    fn states(workers: Vec<WorkerState>) -> SharedWorkerStates {
+        Arc::new(RwLock::new(workers))
+    }
+
+    fn client_ip() -> IpAddr {
+        "127.0.0.1".parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn skips_unhealthy_workers() {
+        let states = states(vec![
+            WorkerState::new_for_test(addr(9001), WorkerStatus::Unhealthy, None),
+            WorkerState::new_for_test(addr(9002), WorkerStatus::Healthy, None),
+            WorkerState::new_for_test(addr(9003), WorkerStatus::Starting, None),
+        ]);
+        let strategy = LeastConnections::new(states).unwrap();
+        for _ in 0..5 {
+            let client = strategy.entry(client_ip()).await.unwrap();
+            assert_eq!(client.socket_addr, addr(9002));
+        }
+    }
+
+    #[tokio::test]
+    async fn falls_back_and_spreads_across_ties_when_no_worker_is_healthy() {
+        let states = states(vec![
+            WorkerState::new_for_test(addr(9001), WorkerStatus::Starting, None),
+            WorkerState::new_for_test(addr(9002), WorkerStatus::Starting, None),
+            WorkerState::new_for_test(addr(9003), WorkerStatus::Starting, None),
+        ]);
+        let strategy = LeastConnections::new(states).unwrap();
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..9 {
+            let client = strategy.entry(client_ip()).await.unwrap();
+            seen.insert(client.socket_addr);
+        }
+        assert_eq!(seen.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn picks_the_least_loaded_healthy_worker() {
+        let states = states(vec![
+            WorkerState::new_for_test(addr(9001), WorkerStatus::Healthy, None),
+            WorkerState::new_for_test(addr(9002), WorkerStatus::Healthy, None),
+        ]);
+        let strategy = LeastConnections::new(states).unwrap();
+
+        // Hold `first`'s Client alive so its in-flight counter stays at 1, making it the more
+        // loaded worker for the next call.
+        let first = strategy.entry(client_ip()).await.unwrap();
+        let second = strategy.entry(client_ip()).await.unwrap();
+        assert_ne!(first.socket_addr, second.socket_addr);
+    }
+}
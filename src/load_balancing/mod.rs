@@ -1,5 +1,6 @@
 mod ip_extractor;
 pub mod ip_hash;
+pub mod least_connections;
 pub mod round_robin;
 
 pub use ip_extractor::IpExtractor;
@@ -8,24 +9,56 @@ use hyper::body::Incoming;
 use hyper::Request;
 
 use crate::client::Client;
-use crate::error::FaucetResult;
-use crate::worker::WorkerState;
+use crate::error::{FaucetError, FaucetResult};
+use crate::metrics::Metrics;
+use crate::rate_limit::{RateLimiter, RateLimiterConfig};
+use crate::worker::{SharedWorkerStates, WorkerState};
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use self::ip_hash::IpHash;
+use self::least_connections::LeastConnections;
 use self::round_robin::RoundRobin;
 
+/// Picks the worker that has gone the longest without being observed unhealthy, used as a
+/// last resort by strategies when no worker is currently healthy. Ties (e.g. a freshly spawned
+/// pool where no worker has failed yet, so every `last_unhealthy_at` is `None`) are broken by
+/// `tiebreak % <number of tied candidates>` rather than always favoring one fixed candidate, so
+/// repeated calls spread across the tied workers instead of funneling all traffic to one of them.
+/// `None` if `workers` is empty — callers must check that themselves (the pool can be scaled to
+/// zero at runtime).
+fn least_recently_failed(workers: &[WorkerState], tiebreak: usize) -> Option<&WorkerState> {
+    let idle: Vec<Duration> = workers
+        .iter()
+        .map(|w| w.last_unhealthy_at().map_or(Duration::MAX, |at| at.elapsed()))
+        .collect();
+    let longest = *idle.iter().max()?;
+    let tied: Vec<usize> = idle
+        .iter()
+        .enumerate()
+        .filter(|(_, idle)| **idle == longest)
+        .map(|(i, _)| i)
+        .collect();
+    tied.get(tiebreak % tied.len()).map(|&i| &workers[i])
+}
+
+/// Error returned by a strategy's `entry` when there are no workers to route to.
+fn no_workers_error() -> FaucetError {
+    FaucetError::Unknown("Cannot balance between zero workers".into())
+}
+
 #[async_trait::async_trait]
 trait LoadBalancingStrategy {
-    async fn entry(&self, ip: IpAddr) -> Client;
+    async fn entry(&self, ip: IpAddr) -> FaucetResult<Client>;
 }
 
 #[derive(Debug, Clone, Copy, clap::ValueEnum)]
 pub enum Strategy {
     RoundRobin,
     IpHash,
+    LeastConnections,
 }
 
 impl FromStr for Strategy {
@@ -34,35 +67,72 @@ impl FromStr for Strategy {
         match s {
             "round_robin" => Ok(Self::RoundRobin),
             "ip_hash" => Ok(Self::IpHash),
+            "least_connections" => Ok(Self::LeastConnections),
             _ => Err("invalid strategy"),
         }
     }
 }
 
+impl Strategy {
+    /// The label used for this strategy's `faucet_strategy_decisions_total` metric.
+    fn as_str(self) -> &'static str {
+        match self {
+            Strategy::RoundRobin => "round_robin",
+            Strategy::IpHash => "ip_hash",
+            Strategy::LeastConnections => "least_connections",
+        }
+    }
+}
+
 type DynLoadBalancer = Arc<dyn LoadBalancingStrategy + Send + Sync>;
 
 pub(crate) struct LoadBalancer {
     strategy: DynLoadBalancer,
+    strategy_name: &'static str,
     extractor: IpExtractor,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl LoadBalancer {
     pub fn new(
         strategy: Strategy,
         extractor: IpExtractor,
-        workers: &[WorkerState],
+        states: SharedWorkerStates,
+        metrics: Arc<Metrics>,
+        rate_limiter_config: Option<RateLimiterConfig>,
     ) -> FaucetResult<Self> {
+        let strategy_name = strategy.as_str();
         let strategy: DynLoadBalancer = match strategy {
-            Strategy::RoundRobin => Arc::new(RoundRobin::new(workers)?),
-            Strategy::IpHash => Arc::new(IpHash::new(workers)?),
+            Strategy::RoundRobin => Arc::new(RoundRobin::new(states)?),
+            Strategy::IpHash => Arc::new(IpHash::new(states)?),
+            Strategy::LeastConnections => Arc::new(LeastConnections::new(states)?),
         };
         Ok(Self {
             strategy,
+            strategy_name,
             extractor,
+            metrics,
+            rate_limiter: RateLimiter::new(rate_limiter_config),
         })
     }
+    /// Checked by the caller right after `extract_ip` and before `get_client`; `false` means the
+    /// request should be rejected, typically with a `429 Too Many Requests`. Always `true` when
+    /// no rate limiter config was supplied to `new`.
+    pub fn check_rate_limit(&self, ip: IpAddr) -> bool {
+        self.rate_limiter.check(ip)
+    }
     pub async fn get_client(&self, ip: IpAddr) -> FaucetResult<Client> {
-        Ok(self.strategy.entry(ip).await)
+        self.metrics.record_client_ip(ip);
+        self.metrics.record_strategy_decision(self.strategy_name);
+
+        let mut client = self.strategy.entry(ip).await?;
+        client.track_in_flight(self.metrics.in_flight_counter(client.socket_addr));
+        // Times the caller's full use of this `Client` (the actual request against the worker),
+        // not the selection above, which is an in-memory index pick and not worth measuring.
+        client.track_latency(Arc::clone(&self.metrics));
+
+        Ok(client)
     }
     pub fn extract_ip(
         &self,
@@ -77,7 +147,10 @@ impl Clone for LoadBalancer {
     fn clone(&self) -> Self {
         Self {
             strategy: Arc::clone(&self.strategy),
+            strategy_name: self.strategy_name,
             extractor: self.extractor,
+            metrics: Arc::clone(&self.metrics),
+            rate_limiter: Arc::clone(&self.rate_limiter),
         }
     }
 }
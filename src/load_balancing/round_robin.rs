@@ -0,0 +1,97 @@
+use super::{least_recently_failed, no_workers_error, LoadBalancingStrategy};
+use crate::client::Client;
+use crate::error::{FaucetError, FaucetResult};
+use crate::worker::SharedWorkerStates;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub(crate) struct RoundRobin {
+    states: SharedWorkerStates,
+    index: AtomicUsize,
+}
+
+impl RoundRobin {
+    pub(crate) fn new(states: SharedWorkerStates) -> FaucetResult<Self> {
+        if states.read().expect("lock poisoned").is_empty() {
+            return Err(FaucetError::Unknown(
+                "Cannot balance between zero workers".into(),
+            ));
+        }
+        Ok(Self {
+            states,
+            index: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl LoadBalancingStrategy for RoundRobin {
+    async fn entry(&self, _ip: IpAddr) -> FaucetResult<Client> {
+        let workers = self.states.read().expect("lock poisoned").clone();
+        if workers.is_empty() {
+            return Err(no_workers_error());
+        }
+        let index = self.index.fetch_add(1, Ordering::Relaxed);
+        let start = index % workers.len();
+        for offset in 0..workers.len() {
+            let candidate = &workers[(start + offset) % workers.len()];
+            if candidate.is_healthy() {
+                return Ok(candidate.client());
+            }
+        }
+        Ok(least_recently_failed(&workers, index)
+            .expect("workers is non-empty, checked above")
+            .client())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::{WorkerState, WorkerStatus};
+    use std::sync::{Arc, RwLock};
+
+    fn addr(port: u16) -> std::net::SocketAddr {
+        format!("127.0.0.1:{port}").parse().unwrap()
+    }
+
+    fn states(workers: Vec<WorkerState>) -> SharedWorkerStates {
+        Arc::new(RwLock::new(workers))
+    }
+
+    fn client_ip() -> IpAddr {
+        "127.0.0.1".parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn skips_unhealthy_workers() {
+        let states = states(vec![
+            WorkerState::new_for_test(addr(9001), WorkerStatus::Unhealthy, None),
+            WorkerState::new_for_test(addr(9002), WorkerStatus::Healthy, None),
+            WorkerState::new_for_test(addr(9003), WorkerStatus::Starting, None),
+        ]);
+        let strategy = RoundRobin::new(states).unwrap();
+        for _ in 0..5 {
+            let client = strategy.entry(client_ip()).await.unwrap();
+            assert_eq!(client.socket_addr, addr(9002));
+        }
+    }
+
+    #[tokio::test]
+    async fn falls_back_and_spreads_across_ties_when_no_worker_is_healthy() {
+        let states = states(vec![
+            WorkerState::new_for_test(addr(9001), WorkerStatus::Starting, None),
+            WorkerState::new_for_test(addr(9002), WorkerStatus::Starting, None),
+            WorkerState::new_for_test(addr(9003), WorkerStatus::Starting, None),
+        ]);
+        let strategy = RoundRobin::new(states).unwrap();
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..9 {
+            let client = strategy.entry(client_ip()).await.unwrap();
+            seen.insert(client.socket_addr);
+        }
+        // Every worker tied at "never failed", so repeated calls must rotate between them
+        // instead of funneling all traffic to one fixed worker.
+        assert_eq!(seen.len(), 3);
+    }
+}
@@ -0,0 +1,356 @@
+use crate::error::FaucetResult;
+use crate::worker::{SharedWorkerStates, WorkerState};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Upper bounds (in seconds) of the latency histogram buckets. Each bucket counts observations
+/// less than or equal to its bound, Prometheus-style.
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// How long a client IP can go unseen before its counter is evicted from `Metrics::client_ips`,
+/// so the `/metrics` endpoint's output doesn't grow without bound under normal traffic.
+const CLIENT_IP_IDLE_WINDOW: Duration = Duration::from_secs(600);
+
+struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    sum_micros: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            sum_micros: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Increments the single bucket `elapsed` falls into, i.e. `buckets[i]` holds a true
+    /// per-bin count; `Metrics::render` re-accumulates these into the cumulative counts
+    /// Prometheus expects for `_bucket{le=...}`.
+    fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        if let Some(bucket) = self
+            .buckets
+            .iter()
+            .zip(LATENCY_BUCKETS)
+            .find(|(_, le)| secs <= *le)
+            .map(|(bucket, _)| bucket)
+        {
+            bucket.fetch_add(1, Ordering::Relaxed);
+        }
+        self.sum_micros
+            .fetch_add(elapsed_micros(elapsed), Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn elapsed_micros(elapsed: Duration) -> u64 {
+    elapsed.as_micros().min(u128::from(u64::MAX)) as u64
+}
+
+/// A client IP's request count and the last time it was seen, so idle entries can be evicted.
+struct ClientIpStats {
+    count: u64,
+    last_seen: Instant,
+}
+
+#[derive(Default)]
+struct WorkerMetrics {
+    requests_routed: AtomicU64,
+    in_flight: Arc<AtomicUsize>,
+    restarts: AtomicU64,
+    latency: LatencyHistogram,
+}
+
+/// Per-worker traffic and health counters, plus global counters for extracted client IPs and
+/// load-balancing decisions, rendered as `/metrics` in Prometheus text exposition format.
+pub(crate) struct Metrics {
+    workers: Mutex<HashMap<SocketAddr, Arc<WorkerMetrics>>>,
+    client_ips: Mutex<HashMap<IpAddr, ClientIpStats>>,
+    strategy_decisions: Mutex<HashMap<&'static str, u64>>,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self {
+            workers: Mutex::new(HashMap::new()),
+            client_ips: Mutex::new(HashMap::new()),
+            strategy_decisions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn worker(&self, addr: SocketAddr) -> Arc<WorkerMetrics> {
+        Arc::clone(
+            self.workers
+                .lock()
+                .expect("lock poisoned")
+                .entry(addr)
+                .or_default(),
+        )
+    }
+
+    /// Drops per-worker metrics for addresses no longer present in `live`, so a restarted or
+    /// scaled-down worker's entry doesn't stay in `/metrics` forever.
+    fn prune_dead_workers(&self, live: &[WorkerState]) {
+        self.workers
+            .lock()
+            .expect("lock poisoned")
+            .retain(|addr, _| live.iter().any(|w| &w.socket_addr == addr));
+    }
+
+    /// Drops client IP counters untouched for longer than [`CLIENT_IP_IDLE_WINDOW`], so the
+    /// map doesn't grow without bound as new IPs are seen over the process lifetime.
+    fn prune_stale_client_ips(&self) {
+        let now = Instant::now();
+        self.client_ips
+            .lock()
+            .expect("lock poisoned")
+            .retain(|_, stats| now.duration_since(stats.last_seen) < CLIENT_IP_IDLE_WINDOW);
+    }
+
+    /// The in-flight gauge for `addr`; the caller is expected to track the returned counter on
+    /// the `Client` it hands out, so it is decremented once that `Client` is dropped.
+    pub(crate) fn in_flight_counter(&self, addr: SocketAddr) -> Arc<AtomicUsize> {
+        Arc::clone(&self.worker(addr).in_flight)
+    }
+
+    pub(crate) fn record_routed(&self, addr: SocketAddr, latency: Duration) {
+        let metrics = self.worker(addr);
+        metrics.requests_routed.fetch_add(1, Ordering::Relaxed);
+        metrics.latency.observe(latency);
+    }
+
+    pub(crate) fn record_restart(&self, addr: SocketAddr) {
+        self.worker(addr).restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_client_ip(&self, ip: IpAddr) {
+        let now = Instant::now();
+        let mut client_ips = self.client_ips.lock().expect("lock poisoned");
+        let stats = client_ips.entry(ip).or_insert_with(|| ClientIpStats {
+            count: 0,
+            last_seen: now,
+        });
+        stats.count += 1;
+        stats.last_seen = now;
+    }
+
+    pub(crate) fn record_strategy_decision(&self, strategy: &'static str) {
+        *self
+            .strategy_decisions
+            .lock()
+            .expect("lock poisoned")
+            .entry(strategy)
+            .or_insert(0) += 1;
+    }
+
+    /// Renders every metric in Prometheus text exposition format. `workers` supplies each
+    /// worker's current health, since that lives on `WorkerState` rather than in `Metrics`.
+    pub(crate) fn render(&self, workers: &[WorkerState]) -> String {
+        self.prune_dead_workers(workers);
+        self.prune_stale_client_ips();
+
+        let mut out = String::new();
+        let worker_metrics = self.workers.lock().expect("lock poisoned");
+
+        out.push_str("# HELP faucet_worker_requests_total Total requests routed to a worker.\n");
+        out.push_str("# TYPE faucet_worker_requests_total counter\n");
+        for (addr, m) in worker_metrics.iter() {
+            let n = m.requests_routed.load(Ordering::Relaxed);
+            out.push_str(&format!("faucet_worker_requests_total{{socket_addr=\"{addr}\"}} {n}\n"));
+        }
+
+        out.push_str("# HELP faucet_worker_in_flight Requests currently in flight to a worker.\n");
+        out.push_str("# TYPE faucet_worker_in_flight gauge\n");
+        for (addr, m) in worker_metrics.iter() {
+            let n = m.in_flight.load(Ordering::Relaxed);
+            out.push_str(&format!("faucet_worker_in_flight{{socket_addr=\"{addr}\"}} {n}\n"));
+        }
+
+        out.push_str("# HELP faucet_worker_restarts_total Total restarts observed for a worker.\n");
+        out.push_str("# TYPE faucet_worker_restarts_total counter\n");
+        for (addr, m) in worker_metrics.iter() {
+            let n = m.restarts.load(Ordering::Relaxed);
+            out.push_str(&format!("faucet_worker_restarts_total{{socket_addr=\"{addr}\"}} {n}\n"));
+        }
+
+        out.push_str("# HELP faucet_worker_healthy Whether a worker is currently healthy (1) or not (0).\n");
+        out.push_str("# TYPE faucet_worker_healthy gauge\n");
+        for worker in workers {
+            out.push_str(&format!(
+                "faucet_worker_healthy{{socket_addr=\"{}\"}} {}\n",
+                worker.socket_addr,
+                worker.is_healthy() as u8
+            ));
+        }
+
+        out.push_str("# HELP faucet_worker_request_duration_seconds Latency of requests routed to a worker.\n");
+        out.push_str("# TYPE faucet_worker_request_duration_seconds histogram\n");
+        for (addr, m) in worker_metrics.iter() {
+            let mut cumulative = 0;
+            for (le, bucket) in LATENCY_BUCKETS.into_iter().zip(m.latency.buckets.iter()) {
+                cumulative += bucket.load(Ordering::Relaxed);
+                out.push_str(&format!(
+                    "faucet_worker_request_duration_seconds_bucket{{socket_addr=\"{addr}\",le=\"{le}\"}} {cumulative}\n"
+                ));
+            }
+            let count = m.latency.count.load(Ordering::Relaxed);
+            out.push_str(&format!(
+                "faucet_worker_request_duration_seconds_bucket{{socket_addr=\"{addr}\",le=\"+Inf\"}} {count}\n"
+            ));
+            let sum = m.latency.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+            out.push_str(&format!(
+                "faucet_worker_request_duration_seconds_sum{{socket_addr=\"{addr}\"}} {sum}\n"
+            ));
+            out.push_str(&format!(
+                "faucet_worker_request_duration_seconds_count{{socket_addr=\"{addr}\"}} {count}\n"
+            ));
+        }
+        drop(worker_metrics);
+
+        out.push_str("# HELP faucet_client_ip_requests_total Requests seen per extracted client IP.\n");
+        out.push_str("# TYPE faucet_client_ip_requests_total counter\n");
+        for (ip, stats) in self.client_ips.lock().expect("lock poisoned").iter() {
+            out.push_str(&format!(
+                "faucet_client_ip_requests_total{{ip=\"{ip}\"}} {}\n",
+                stats.count
+            ));
+        }
+
+        out.push_str(
+            "# HELP faucet_strategy_decisions_total Routing decisions made by the load-balancing strategy.\n",
+        );
+        out.push_str("# TYPE faucet_strategy_decisions_total counter\n");
+        for (strategy, count) in self.strategy_decisions.lock().expect("lock poisoned").iter() {
+            out.push_str(&format!(
+                "faucet_strategy_decisions_total{{strategy=\"{strategy}\"}} {count}\n"
+            ));
+        }
+
+        out
+    }
+}
+
+/// Configuration for the admin listener that exposes `/metrics`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AdminConfig {
+    /// Port to bind the admin listener on. `None` disables the admin server entirely.
+    pub port: Option<u16>,
+}
+
+async fn handle_admin_connection(mut stream: TcpStream, metrics: Arc<Metrics>, states: SharedWorkerStates) {
+    let mut buf = [0u8; 1024];
+    let Ok(Ok(n)) = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf)).await else {
+        return;
+    };
+
+    let is_metrics = String::from_utf8_lossy(&buf[..n])
+        .lines()
+        .next()
+        .is_some_and(|line| line.starts_with("GET /metrics "));
+
+    let (status, body) = if is_metrics {
+        let workers = states.read().expect("lock poisoned").clone();
+        ("200 OK", metrics.render(&workers))
+    } else {
+        ("404 Not Found", String::new())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Serves `/metrics` in Prometheus text exposition format until the process exits. Returns
+/// immediately without binding anything if `config.port` is `None`.
+pub(crate) async fn serve_admin(
+    config: AdminConfig,
+    metrics: Arc<Metrics>,
+    states: SharedWorkerStates,
+) -> FaucetResult<()> {
+    let Some(port) = config.port else {
+        return Ok(());
+    };
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        tokio::spawn(handle_admin_connection(
+            stream,
+            Arc::clone(&metrics),
+            Arc::clone(&states),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(last_octet: u8) -> IpAddr {
+        IpAddr::from([127, 0, 0, last_octet])
+    }
+
+    #[test]
+    fn observe_places_each_sample_in_exactly_one_bucket() {
+        let histogram = LatencyHistogram::default();
+        for secs in [0.001, 0.03, 0.2] {
+            histogram.observe(Duration::from_secs_f64(secs));
+        }
+        // 0.001 <= le=0.005 (index 0), 0.03 <= le=0.05 (index 3), 0.2 <= le=0.25 (index 5).
+        // Per-bin, not cumulative: each sample lands in exactly one bucket.
+        let counts: Vec<u64> = histogram
+            .buckets
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .collect();
+        assert_eq!(counts, vec![1, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(histogram.count.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn record_client_ip_counts_repeated_requests_from_same_ip() {
+        let metrics = Metrics::new();
+        let client = ip(1);
+        metrics.record_client_ip(client);
+        metrics.record_client_ip(client);
+        metrics.record_client_ip(ip(2));
+
+        let client_ips = metrics.client_ips.lock().expect("lock poisoned");
+        assert_eq!(client_ips.get(&client).unwrap().count, 2);
+        assert_eq!(client_ips.get(&ip(2)).unwrap().count, 1);
+    }
+
+    #[test]
+    fn stale_client_ips_are_evicted_but_fresh_ones_survive() {
+        let metrics = Metrics::new();
+        let stale = ip(1);
+        let fresh = ip(2);
+        metrics.client_ips.lock().expect("lock poisoned").insert(
+            stale,
+            ClientIpStats {
+                count: 1,
+                last_seen: Instant::now() - CLIENT_IP_IDLE_WINDOW - Duration::from_secs(1),
+            },
+        );
+        metrics.record_client_ip(fresh);
+
+        metrics.prune_stale_client_ips();
+
+        let client_ips = metrics.client_ips.lock().expect("lock poisoned");
+        assert!(!client_ips.contains_key(&stale));
+        assert!(client_ips.contains_key(&fresh));
+    }
+}
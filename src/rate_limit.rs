@@ -0,0 +1,190 @@
+use dashmap::DashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Configuration for the per-client-IP token-bucket rate limiter.
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimiterConfig {
+    /// Tokens added to a bucket per second.
+    pub rate: f64,
+    /// Maximum tokens a bucket can hold, i.e. the largest burst a client can send at once.
+    pub burst: f64,
+    /// How long a bucket can go untouched before the background sweep evicts it.
+    pub idle_window: Duration,
+}
+
+/// Per-client-IP token-bucket rate limiter, meant to be checked in the request path right after
+/// [`IpExtractor::extract`](crate::load_balancing::IpExtractor::extract) and before
+/// [`LoadBalancer::get_client`](crate::load_balancing::LoadBalancer::get_client). Disabled by
+/// default: construct with `config: None` to make [`RateLimiter::check`] always allow.
+pub(crate) struct RateLimiter {
+    config: Option<RateLimiterConfig>,
+    buckets: DashMap<IpAddr, TokenBucket>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: Option<RateLimiterConfig>) -> Arc<Self> {
+        let limiter = Arc::new(Self {
+            config,
+            buckets: DashMap::new(),
+        });
+        if config.is_some() {
+            tokio::spawn(Arc::clone(&limiter).sweep_idle_forever());
+        }
+        limiter
+    }
+
+    /// Lazily refills `ip`'s bucket and consumes one token if available. Returns `true` if the
+    /// request is allowed to proceed; `false` means the caller should reject it (e.g. with a
+    /// `429 Too Many Requests`). Always `true` when the limiter is disabled.
+    pub(crate) fn check(&self, ip: IpAddr) -> bool {
+        let Some(config) = self.config else {
+            return true;
+        };
+
+        let now = Instant::now();
+        let mut bucket = self.buckets.entry(ip).or_insert_with(|| TokenBucket {
+            tokens: config.burst,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * config.rate).min(config.burst);
+        bucket.last_refill = now;
+
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    /// Periodically evicts buckets untouched for `idle_window`, so memory stays bounded under
+    /// IP churn. Runs until the process exits; spawned as a background task by `new`.
+    async fn sweep_idle_forever(self: Arc<Self>) {
+        let Some(config) = self.config else {
+            return;
+        };
+        loop {
+            tokio::time::sleep(config.idle_window).await;
+            self.evict_idle(config);
+        }
+    }
+
+    /// Drops every bucket untouched for at least `config.idle_window`.
+    fn evict_idle(&self, config: RateLimiterConfig) {
+        let now = Instant::now();
+        self.buckets
+            .retain(|_, bucket| now.duration_since(bucket.last_refill) < config.idle_window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(rate: f64, burst: f64) -> RateLimiterConfig {
+        RateLimiterConfig {
+            rate,
+            burst,
+            idle_window: Duration::from_secs(60),
+        }
+    }
+
+    fn ip(last_octet: u8) -> IpAddr {
+        IpAddr::from([127, 0, 0, last_octet])
+    }
+
+    #[test]
+    fn disabled_limiter_always_allows() {
+        let limiter = RateLimiter {
+            config: None,
+            buckets: DashMap::new(),
+        };
+        for _ in 0..100 {
+            assert!(limiter.check(ip(1)));
+        }
+    }
+
+    #[test]
+    fn fresh_bucket_starts_at_burst_then_rejects_once_exhausted() {
+        let limiter = RateLimiter {
+            config: Some(config(1.0, 3.0)),
+            buckets: DashMap::new(),
+        };
+        let client = ip(1);
+        assert!(limiter.check(client));
+        assert!(limiter.check(client));
+        assert!(limiter.check(client));
+        // No time has passed to refill, so a 4th request right away must be rejected.
+        assert!(!limiter.check(client));
+    }
+
+    #[test]
+    fn refill_is_capped_at_burst() {
+        let limiter = RateLimiter {
+            config: Some(config(100.0, 2.0)),
+            buckets: DashMap::new(),
+        };
+        let client = ip(1);
+        limiter.buckets.insert(
+            client,
+            TokenBucket {
+                tokens: 0.0,
+                last_refill: Instant::now() - Duration::from_secs(10),
+            },
+        );
+        // 10s * 100 tokens/s would be 1000 tokens, but burst caps the bucket at 2.
+        assert!(limiter.check(client));
+        assert!(limiter.check(client));
+        assert!(!limiter.check(client));
+    }
+
+    #[test]
+    fn different_ips_get_independent_buckets() {
+        let limiter = RateLimiter {
+            config: Some(config(1.0, 1.0)),
+            buckets: DashMap::new(),
+        };
+        assert!(limiter.check(ip(1)));
+        assert!(!limiter.check(ip(1)));
+        // A different client's bucket hasn't been touched, so it still has its full burst.
+        assert!(limiter.check(ip(2)));
+    }
+
+    #[test]
+    fn evict_idle_removes_stale_buckets_and_keeps_fresh_ones() {
+        let config = config(1.0, 1.0);
+        let limiter = RateLimiter {
+            config: Some(config),
+            buckets: DashMap::new(),
+        };
+        let stale = ip(1);
+        let fresh = ip(2);
+        limiter.buckets.insert(
+            stale,
+            TokenBucket {
+                tokens: 1.0,
+                last_refill: Instant::now() - config.idle_window - Duration::from_secs(1),
+            },
+        );
+        limiter.buckets.insert(
+            fresh,
+            TokenBucket {
+                tokens: 1.0,
+                last_refill: Instant::now(),
+            },
+        );
+
+        limiter.evict_idle(config);
+
+        assert!(!limiter.buckets.contains_key(&stale));
+        assert!(limiter.buckets.contains_key(&fresh));
+    }
+}
@@ -1,13 +1,202 @@
+use crate::client::Client;
 use crate::error::{FaucetError, FaucetResult};
+use crate::metrics::Metrics;
+use rand::Rng;
 use std::{
     net::SocketAddr,
     path::Path,
-    sync::{atomic::AtomicBool, Arc},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    process::Child,
+    sync::Notify,
+    task::JoinHandle,
+    time::Instant,
 };
-use tokio::{process::Child, task::JoinHandle};
 use tokio_stream::StreamExt;
 use tokio_util::codec::{FramedRead, LinesCodec};
 
+/// Default base delay for the respawn backoff (before jitter is applied).
+const DEFAULT_BASE_DELAY: Duration = Duration::from_secs(1);
+/// Default upper bound on the respawn backoff delay.
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(300);
+/// How long a worker must stay alive before its failure counter is reset.
+const DEFAULT_STABILITY_THRESHOLD: Duration = Duration::from_secs(30);
+/// Default interval between health-check probes.
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Default timeout for a single health-check probe.
+const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Health of a worker, as observed by its background health-check task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum WorkerStatus {
+    /// The worker has not been probed yet; a failed first probe marks it `Unhealthy`.
+    Starting = 0,
+    Healthy = 1,
+    Unhealthy = 2,
+}
+
+impl From<u8> for WorkerStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => WorkerStatus::Healthy,
+            2 => WorkerStatus::Unhealthy,
+            _ => WorkerStatus::Starting,
+        }
+    }
+}
+
+/// How a worker's health is probed.
+#[derive(Clone, Debug)]
+pub(crate) struct HealthCheckConfig {
+    /// Time between probes.
+    pub(crate) interval: Duration,
+    /// Time allowed for a single probe (TCP connect + optional HTTP request).
+    pub(crate) timeout: Duration,
+    /// When set, an HTTP `GET` is issued against this path after the TCP connect succeeds.
+    pub(crate) path: Option<String>,
+}
+
+impl Default for HealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+            timeout: DEFAULT_HEALTH_CHECK_TIMEOUT,
+            path: None,
+        }
+    }
+}
+
+/// The live set of worker states, shared between [`Workers`] (which writes a fresh snapshot
+/// whenever workers are spawned, scaled, or restarted) and the load-balancing strategies (which
+/// read the current generation on every `entry` call instead of a snapshot frozen at
+/// construction time).
+pub(crate) type SharedWorkerStates = Arc<RwLock<Vec<WorkerState>>>;
+
+/// A read-only, cheaply cloneable view of a worker, shared with the load balancer so it can
+/// see health updates as they happen.
+#[derive(Clone)]
+pub(crate) struct WorkerState {
+    pub(crate) socket_addr: SocketAddr,
+    status: Arc<AtomicU8>,
+    last_unhealthy_at: Arc<Mutex<Option<Instant>>>,
+    pid: Arc<AtomicU32>,
+}
+
+impl WorkerState {
+    pub(crate) fn status(&self) -> WorkerStatus {
+        WorkerStatus::from(self.status.load(Ordering::Relaxed))
+    }
+    pub(crate) fn is_healthy(&self) -> bool {
+        self.status() == WorkerStatus::Healthy
+    }
+    /// When this worker was last observed unhealthy, or `None` if it never has been.
+    pub(crate) fn last_unhealthy_at(&self) -> Option<Instant> {
+        *self.last_unhealthy_at.lock().expect("lock poisoned")
+    }
+    /// PID of the worker's current underlying process, updated across respawns.
+    pub(crate) fn pid(&self) -> u32 {
+        self.pid.load(Ordering::Relaxed)
+    }
+    pub(crate) fn client(&self) -> Client {
+        Client::new(self.socket_addr)
+    }
+}
+
+#[cfg(test)]
+impl WorkerState {
+    /// Builds a `WorkerState` with an arbitrary status and `last_unhealthy_at`, so load-balancing
+    /// strategy tests can force a worker's health without spinning up a real process.
+    pub(crate) fn new_for_test(
+        socket_addr: SocketAddr,
+        status: WorkerStatus,
+        last_unhealthy_at: Option<Instant>,
+    ) -> Self {
+        Self {
+            socket_addr,
+            status: Arc::new(AtomicU8::new(status as u8)),
+            last_unhealthy_at: Arc::new(Mutex::new(last_unhealthy_at)),
+            pid: Arc::new(AtomicU32::new(0)),
+        }
+    }
+}
+
+async fn probe_worker_health(addr: SocketAddr, config: &HealthCheckConfig) -> bool {
+    let stream = match tokio::time::timeout(config.timeout, TcpStream::connect(addr)).await {
+        Ok(Ok(stream)) => stream,
+        _ => return false,
+    };
+
+    let Some(path) = &config.path else {
+        return true;
+    };
+
+    probe_http_path(stream, addr, path, config.timeout).await
+}
+
+async fn probe_http_path(
+    mut stream: TcpStream,
+    addr: SocketAddr,
+    path: &str,
+    timeout: Duration,
+) -> bool {
+    let request = format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n");
+    if tokio::time::timeout(timeout, stream.write_all(request.as_bytes()))
+        .await
+        .is_err()
+    {
+        return false;
+    }
+
+    let mut response = Vec::new();
+    if tokio::time::timeout(timeout, stream.read_to_end(&mut response))
+        .await
+        .is_err()
+    {
+        return false;
+    }
+
+    response
+        .split(|&b| b == b' ')
+        .nth(1)
+        .and_then(|code| std::str::from_utf8(code).ok())
+        .and_then(|code| code.parse::<u16>().ok())
+        .is_some_and(|code| (200..400).contains(&code))
+}
+
+fn spawn_health_check_task(
+    state: WorkerState,
+    stop_notify: Arc<Notify>,
+    config: HealthCheckConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            // Probe immediately rather than sleeping first, so a freshly spawned worker doesn't
+            // sit in `WorkerStatus::Starting` (treated as unhealthy by every strategy) for a
+            // full `config.interval` before it gets its first chance to be marked healthy.
+            let healthy = probe_worker_health(state.socket_addr, &config).await;
+            if healthy {
+                state.status.store(WorkerStatus::Healthy as u8, Ordering::Relaxed);
+            } else {
+                state.status.store(WorkerStatus::Unhealthy as u8, Ordering::Relaxed);
+                *state.last_unhealthy_at.lock().expect("lock poisoned") = Some(Instant::now());
+            }
+
+            tokio::select! {
+                _ = stop_notify.notified() => return,
+                _ = tokio::time::sleep(config.interval) => {}
+            }
+        }
+    })
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum WorkerType {
     Plumber,
@@ -105,12 +294,47 @@ impl WorkerType {
     }
 }
 
+/// Policy governing how long to wait before respawning a crashed worker.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RestartPolicy {
+    /// Delay used for the first restart attempt.
+    base_delay: Duration,
+    /// Upper bound on the restart delay, regardless of how many failures occurred.
+    max_delay: Duration,
+    /// How long a worker must stay up before its failure count is reset to zero.
+    stability_threshold: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            stability_threshold: DEFAULT_STABILITY_THRESHOLD,
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Delay for the `failures`-th consecutive restart, with `[0.5, 1.5]` jitter applied.
+    fn delay_for(&self, failures: u32) -> Duration {
+        let exponent = failures.saturating_sub(1).min(32);
+        let backoff_secs =
+            (self.base_delay.as_secs_f64() * 2f64.powi(exponent as i32)).min(self.max_delay.as_secs_f64());
+        let jitter = rand::thread_rng().gen_range(0.5..1.5);
+        Duration::from_secs_f64((backoff_secs * jitter).clamp(0.0, self.max_delay.as_secs_f64()))
+    }
+}
+
 struct Worker {
     /// Whether the worker should be stopped
     stop: Arc<AtomicBool>,
+    /// Notified when the worker should stop, so a sleeping backoff wakes up immediately.
+    stop_notify: Arc<Notify>,
     _worker_task: JoinHandle<FaucetResult<()>>,
-    /// The address of the worker's socket.
-    socket_addr: SocketAddr,
+    _health_check_task: JoinHandle<()>,
+    /// Shared, cheaply cloneable view of this worker's address and health.
+    state: WorkerState,
 }
 fn get_available_socket() -> FaucetResult<SocketAddr> {
     use std::net::TcpListener;
@@ -122,35 +346,96 @@ fn get_available_socket() -> FaucetResult<SocketAddr> {
 fn spawn_worker_task(
     addr: SocketAddr,
     stop: Arc<AtomicBool>,
+    stop_notify: Arc<Notify>,
     worker_type: WorkerType,
     workdir: Arc<Path>,
+    restart_policy: RestartPolicy,
+    pid_state: Arc<AtomicU32>,
+    metrics: Arc<Metrics>,
 ) -> JoinHandle<FaucetResult<()>> {
     tokio::spawn(async move {
-        let stop = Arc::clone(&stop);
+        let mut consecutive_failures: u32 = 0;
         let mut child = worker_type.spawn_process(workdir.clone(), addr.port())?;
-        let pid = child.id().expect("Failed to get plumber worker PID");
+        let mut pid = child.id().expect("Failed to get plumber worker PID");
+        pid_state.store(pid, Ordering::Relaxed);
         loop {
-            if stop.clone().load(std::sync::atomic::Ordering::SeqCst) {
+            let started_at = Instant::now();
+            let status = tokio::select! {
+                _ = stop_notify.notified() => {
+                    log::warn!("Worker::{} received stop signal", pid);
+                    return Ok(());
+                }
+                status = child.wait() => status?,
+            };
+            if stop.load(std::sync::atomic::Ordering::SeqCst) {
                 log::warn!("Worker::{} received stop signal", pid);
                 return Ok(());
             }
-            let status = child.wait().await?;
             log::error!(target: "faucet", "Worker::{} exited with status {}", pid, status);
+
+            if started_at.elapsed() >= restart_policy.stability_threshold {
+                consecutive_failures = 0;
+            }
+            consecutive_failures += 1;
+
+            metrics.record_restart(addr);
+
+            let delay = restart_policy.delay_for(consecutive_failures);
+            log::warn!(
+                target: "faucet",
+                "Worker::{} restarting in {:?} (consecutive failures: {})",
+                pid, delay, consecutive_failures
+            );
+            tokio::select! {
+                _ = stop_notify.notified() => {
+                    log::warn!("Worker::{} received stop signal while backing off", pid);
+                    return Ok(());
+                }
+                _ = tokio::time::sleep(delay) => {}
+            }
+
             child = worker_type.spawn_process(workdir.clone(), addr.port())?;
+            pid = child.id().expect("Failed to get plumber worker PID");
+            pid_state.store(pid, Ordering::Relaxed);
         }
     })
 }
 
 impl Worker {
-    pub fn new(worker_type: WorkerType, workdir: Arc<Path>) -> FaucetResult<Self> {
+    pub fn new(
+        worker_type: WorkerType,
+        workdir: Arc<Path>,
+        restart_policy: RestartPolicy,
+        health_check: HealthCheckConfig,
+        metrics: Arc<Metrics>,
+    ) -> FaucetResult<Self> {
         let stop = Arc::new(AtomicBool::new(false));
+        let stop_notify = Arc::new(Notify::new());
         let socket_addr = get_available_socket()?;
-        let worker_task =
-            spawn_worker_task(socket_addr, Arc::clone(&stop), worker_type, workdir.clone());
+        let state = WorkerState {
+            socket_addr,
+            status: Arc::new(AtomicU8::new(WorkerStatus::Starting as u8)),
+            last_unhealthy_at: Arc::new(Mutex::new(None)),
+            pid: Arc::new(AtomicU32::new(0)),
+        };
+        let worker_task = spawn_worker_task(
+            socket_addr,
+            Arc::clone(&stop),
+            Arc::clone(&stop_notify),
+            worker_type,
+            workdir.clone(),
+            restart_policy,
+            Arc::clone(&state.pid),
+            metrics,
+        );
+        let health_check_task =
+            spawn_health_check_task(state.clone(), Arc::clone(&stop_notify), health_check);
         Ok(Self {
             stop,
+            stop_notify,
             _worker_task: worker_task,
-            socket_addr,
+            _health_check_task: health_check_task,
+            state,
         })
     }
 }
@@ -158,35 +443,152 @@ impl Worker {
 impl Drop for Worker {
     fn drop(&mut self) {
         self.stop.store(true, std::sync::atomic::Ordering::SeqCst);
+        self.stop_notify.notify_waiters();
     }
 }
 
+/// A point-in-time snapshot of one worker's address, PID, and health, returned by
+/// [`Workers::status`].
+#[derive(Clone, Debug)]
+pub(crate) struct WorkerSnapshot {
+    pub(crate) socket_addr: SocketAddr,
+    pub(crate) pid: u32,
+    pub(crate) status: WorkerStatus,
+}
+
 pub(crate) struct Workers {
     workers: Vec<Worker>,
+    /// The live worker set, shared with the [`LoadBalancer`](crate::load_balancing::LoadBalancer)
+    /// so strategies route over the current pool rather than a snapshot taken once at startup.
+    states: SharedWorkerStates,
     worker_type: WorkerType,
     workdir: Arc<Path>,
+    restart_policy: RestartPolicy,
+    health_check: HealthCheckConfig,
+    metrics: Arc<Metrics>,
 }
 
 impl Workers {
-    pub(crate) fn new(worker_type: WorkerType, workdir: impl AsRef<Path>) -> Self {
+    pub(crate) fn new(
+        worker_type: WorkerType,
+        workdir: impl AsRef<Path>,
+        restart_policy: RestartPolicy,
+        health_check: HealthCheckConfig,
+        metrics: Arc<Metrics>,
+    ) -> Self {
         let workdir = workdir.as_ref();
         Self {
             workers: Vec::new(),
+            states: Arc::new(RwLock::new(Vec::new())),
             worker_type,
             workdir: workdir.into(),
+            restart_policy,
+            health_check,
+            metrics,
         }
     }
+    /// Publishes the current worker set so the load balancer sees it on the next `entry` call.
+    fn sync_states(&self) {
+        *self.states.write().expect("lock poisoned") =
+            self.workers.iter().map(|w| w.state.clone()).collect();
+    }
     pub(crate) fn spawn(&mut self, n: usize) -> FaucetResult<()> {
         for _ in 0..n {
-            self.workers
-                .push(Worker::new(self.worker_type, self.workdir.clone())?);
+            self.workers.push(Worker::new(
+                self.worker_type,
+                self.workdir.clone(),
+                self.restart_policy,
+                self.health_check.clone(),
+                Arc::clone(&self.metrics),
+            )?);
+        }
+        self.sync_states();
+        Ok(())
+    }
+    /// Spawns or drops workers so the pool has exactly `n` of them. Dropped workers go through
+    /// the usual [`Worker`] `Drop` path, which signals their task to stop.
+    pub(crate) fn scale_to(&mut self, n: usize) -> FaucetResult<()> {
+        match n.cmp(&self.workers.len()) {
+            std::cmp::Ordering::Greater => return self.spawn(n - self.workers.len()),
+            std::cmp::Ordering::Less => self.workers.truncate(n),
+            std::cmp::Ordering::Equal => return Ok(()),
         }
+        self.sync_states();
+        Ok(())
+    }
+    /// Stops and replaces the worker at `index` in place, e.g. to recover one stuck worker
+    /// without touching the rest of the pool.
+    pub(crate) fn restart(&mut self, index: usize) -> FaucetResult<()> {
+        let worker = self
+            .workers
+            .get_mut(index)
+            .ok_or_else(|| FaucetError::Unknown(format!("No worker at index {index}")))?;
+        *worker = Worker::new(
+            self.worker_type,
+            self.workdir.clone(),
+            self.restart_policy,
+            self.health_check.clone(),
+            Arc::clone(&self.metrics),
+        )?;
+        self.sync_states();
         Ok(())
     }
-    pub(crate) fn get_socket_addrs(&self) -> Vec<SocketAddr> {
+    /// A snapshot of each worker's address, PID, and health, in pool order.
+    pub(crate) fn status(&self) -> Vec<WorkerSnapshot> {
         self.workers
             .iter()
-            .map(|w| w.socket_addr)
-            .collect::<Vec<_>>()
+            .map(|w| WorkerSnapshot {
+                socket_addr: w.state.socket_addr,
+                pid: w.state.pid(),
+                status: w.state.status(),
+            })
+            .collect()
+    }
+    /// The live worker set, to be handed to [`LoadBalancer::new`](crate::load_balancing::LoadBalancer::new).
+    pub(crate) fn shared_states(&self) -> SharedWorkerStates {
+        Arc::clone(&self.states)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(300),
+            stability_threshold: Duration::from_secs(30),
+        }
+    }
+
+    #[test]
+    fn delay_for_zero_failures_is_within_jittered_base_delay() {
+        let policy = policy();
+        let delay = policy.delay_for(0);
+        assert!(delay >= Duration::from_secs_f64(0.5));
+        assert!(delay <= Duration::from_secs_f64(1.5));
+    }
+
+    #[test]
+    fn delay_for_saturates_at_max_delay() {
+        let policy = policy();
+        for failures in [33, 1000, u32::MAX] {
+            let delay = policy.delay_for(failures);
+            assert!(delay <= policy.max_delay);
+            // Even with the minimum jitter multiplier, a saturated backoff stays close to max_delay.
+            assert!(delay >= Duration::from_secs_f64(policy.max_delay.as_secs_f64() * 0.5));
+        }
+    }
+
+    #[test]
+    fn delay_for_grows_with_failures() {
+        let policy = policy();
+        // Compare against the jittered bounds (jitter spans 0.5x to 1.5x) so the assertion
+        // isn't flaky: failures=5's minimum possible delay still exceeds failures=1's maximum.
+        let early = policy.delay_for(1);
+        let later = policy.delay_for(5);
+        assert!(early <= Duration::from_secs_f64(1.5));
+        assert!(later >= Duration::from_secs_f64(8.0));
     }
 }